@@ -1,5 +1,14 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::ops::{Add, Sub};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
 use crate::{IntCounterWithLabels, Labels};
 use prometheus::core::{Atomic, AtomicF64, AtomicI64, GenericCounter, GenericGauge, Number};
+use prometheus::{Histogram, HistogramVec};
 
 /// An RAII-style guard for an [`AtomicI64`] gauge.
 ///
@@ -11,6 +20,39 @@ pub type IntGaugeGuard = GenericGaugeGuard<AtomicI64>;
 /// Created by the methods on the [`GuardedGauge`] extension trait.
 pub type GaugeGuard = GenericGaugeGuard<AtomicF64>;
 
+/// Replace `*value` with `new`, applying the difference to `gauge` immediately. Shared by
+/// [`GenericGaugeGuard::set`] and [`OwnedGaugeGuard::set`].
+fn gauge_guard_set<P: Atomic + 'static>(gauge: &GenericGauge<P>, value: &mut P::T, new: P::T)
+where
+    P::T: Sub<Output = P::T>,
+{
+    // `P::T` is always `i64` or `f64`, both of which implement `Sub` directly, so compute the
+    // delta without a lossy `into_f64`/`from_f64` round trip.
+    let delta = new - *value;
+    gauge.add(delta);
+    *value = new;
+}
+
+/// Increase `*value` by `delta`, applying it to `gauge` immediately. Shared by
+/// [`GenericGaugeGuard::add`] and [`OwnedGaugeGuard::add`].
+fn gauge_guard_add<P: Atomic + 'static>(gauge: &GenericGauge<P>, value: &mut P::T, delta: P::T)
+where
+    P::T: Add<Output = P::T>,
+{
+    gauge.add(delta);
+    *value = *value + delta;
+}
+
+/// Decrease `*value` by `delta`, applying it to `gauge` immediately. Shared by
+/// [`GenericGaugeGuard::sub`] and [`OwnedGaugeGuard::sub`].
+fn gauge_guard_sub<P: Atomic + 'static>(gauge: &GenericGauge<P>, value: &mut P::T, delta: P::T)
+where
+    P::T: Sub<Output = P::T>,
+{
+    gauge.sub(delta);
+    *value = *value - delta;
+}
+
 /// An RAII-style guard for situations where we want to increment a gauge and then ensure that there
 /// is always a corresponding decrement.
 ///
@@ -27,6 +69,35 @@ impl<P: Atomic + 'static> Drop for GenericGaugeGuard<P> {
     }
 }
 
+impl<P: Atomic + 'static> GenericGaugeGuard<P> {
+    /// Replace the tracked amount with `new`, applying the difference to the gauge immediately.
+    ///
+    /// The guard will subtract `new` (rather than whatever amount was tracked before) from the
+    /// gauge when it is dropped.
+    pub fn set(&mut self, new: P::T)
+    where
+        P::T: Sub<Output = P::T>,
+    {
+        gauge_guard_set(self.gauge, &mut self.value, new);
+    }
+
+    /// Increase the tracked amount by `delta`, applying it to the gauge immediately.
+    pub fn add(&mut self, delta: P::T)
+    where
+        P::T: Add<Output = P::T>,
+    {
+        gauge_guard_add(self.gauge, &mut self.value, delta);
+    }
+
+    /// Decrease the tracked amount by `delta`, applying it to the gauge immediately.
+    pub fn sub(&mut self, delta: P::T)
+    where
+        P::T: Sub<Output = P::T>,
+    {
+        gauge_guard_sub(self.gauge, &mut self.value, delta);
+    }
+}
+
 /// An extension trait for [`GenericGauge`] to provide methods for temporarily modifying a gauge.
 pub trait GuardedGauge<P: Atomic + 'static> {
     /// Increase the gauge by 1 while the guard exists.
@@ -36,6 +107,18 @@ pub trait GuardedGauge<P: Atomic + 'static> {
     /// Increase the gauge by the given increment while the guard exists.
     #[must_use]
     fn guarded_add(&'static self, v: P::T) -> GenericGaugeGuard<P>;
+
+    /// Like [`guarded_inc`](Self::guarded_inc), but holds an owned clone of the gauge instead of
+    /// a `'static` reference, so it can guard a gauge owned inside an `Arc` or constructed at
+    /// runtime.
+    #[must_use]
+    fn guarded_inc_owned(&self) -> OwnedGaugeGuard<P>;
+
+    /// Like [`guarded_add`](Self::guarded_add), but holds an owned clone of the gauge instead of
+    /// a `'static` reference, so it can guard a gauge owned inside an `Arc` or constructed at
+    /// runtime.
+    #[must_use]
+    fn guarded_add_owned(&self, v: P::T) -> OwnedGaugeGuard<P>;
 }
 
 impl<P: Atomic + 'static> GuardedGauge<P> for GenericGauge<P> {
@@ -54,6 +137,251 @@ impl<P: Atomic + 'static> GuardedGauge<P> for GenericGauge<P> {
             gauge: self,
         }
     }
+
+    fn guarded_inc_owned(&self) -> OwnedGaugeGuard<P> {
+        self.inc();
+        OwnedGaugeGuard {
+            value: <P::T as Number>::from_i64(1),
+            gauge: self.clone(),
+        }
+    }
+
+    fn guarded_add_owned(&self, v: P::T) -> OwnedGaugeGuard<P> {
+        self.add(v);
+        OwnedGaugeGuard {
+            value: v,
+            gauge: self.clone(),
+        }
+    }
+}
+
+/// An RAII-style guard identical to [`GenericGaugeGuard`], except that it holds an owned clone of
+/// the gauge (cheap, since a [`GenericGauge`] is internally an `Arc`) rather than a `'static`
+/// reference.
+///
+/// Created by the methods on the [`GuardedGauge`] extension trait.
+pub struct OwnedGaugeGuard<P: Atomic + 'static> {
+    value: P::T,
+    gauge: GenericGauge<P>,
+}
+
+/// When an owned gauge guard is dropped, it will perform the corresponding decrement.
+impl<P: Atomic + 'static> Drop for OwnedGaugeGuard<P> {
+    fn drop(&mut self) {
+        self.gauge.sub(self.value);
+    }
+}
+
+impl<P: Atomic + 'static> OwnedGaugeGuard<P> {
+    /// Replace the tracked amount with `new`, applying the difference to the gauge immediately.
+    ///
+    /// The guard will subtract `new` (rather than whatever amount was tracked before) from the
+    /// gauge when it is dropped.
+    pub fn set(&mut self, new: P::T)
+    where
+        P::T: Sub<Output = P::T>,
+    {
+        gauge_guard_set(&self.gauge, &mut self.value, new);
+    }
+
+    /// Increase the tracked amount by `delta`, applying it to the gauge immediately.
+    pub fn add(&mut self, delta: P::T)
+    where
+        P::T: Add<Output = P::T>,
+    {
+        gauge_guard_add(&self.gauge, &mut self.value, delta);
+    }
+
+    /// Decrease the tracked amount by `delta`, applying it to the gauge immediately.
+    pub fn sub(&mut self, delta: P::T)
+    where
+        P::T: Sub<Output = P::T>,
+    {
+        gauge_guard_sub(&self.gauge, &mut self.value, delta);
+    }
+}
+
+/// A single atomic slot holding the mirrored count of a [`HotLabelCache`]'s hot label
+/// combination. Reading it skips the counter's per-label map; the real increment does not.
+struct HotLabelSlot(AtomicU64);
+
+impl HotLabelSlot {
+    fn new() -> Self {
+        Self(AtomicU64::new(0))
+    }
+
+    fn add(&self, v: u64) {
+        self.0.fetch_add(v, Ordering::Relaxed);
+    }
+
+    fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// A cache for a single "hot" label combination -- typically the zero-label case, or a
+/// user-declared combination expected to dominate traffic -- that [`DeferredAddWithLabels`] can
+/// mirror increments into via [`via_hot_cache`](DeferredAddWithLabels::via_hot_cache).
+///
+/// This cache holds no pointer into `IntCounterWithLabels`' internals and does not make its
+/// `add`/`inc` skip their hashmap lookup. It is *not* a fast path that replaces the real increment: `DeferredAddWithLabels` always
+/// still increments the real series, and additionally mirrors matching label combinations in
+/// here, for callers who want to read a hot combination's running value (via
+/// [`get`](HotLabelCache::get)) without going through the counter/registry at all.
+pub struct HotLabelCache<L> {
+    hot: L,
+    slot: HotLabelSlot,
+}
+
+impl<L> HotLabelCache<L> {
+    /// Create a cache for the "hot" label combination `hot`.
+    pub fn new(hot: L) -> Self {
+        Self {
+            hot,
+            slot: HotLabelSlot::new(),
+        }
+    }
+
+    /// Whether `labels` matches the registered hot combination.
+    fn matches(&self, labels: &L) -> bool
+    where
+        L: PartialEq,
+    {
+        &self.hot == labels
+    }
+
+    /// Mirror an increment of `v` into the cached slot. Does not touch the real series.
+    fn mirror(&self, v: u64) {
+        self.slot.add(v);
+    }
+
+    /// The current accumulated value mirrored into this cache.
+    pub fn get(&self) -> u64 {
+        self.slot.get()
+    }
+}
+
+/// A small set of label pairs (typically a `trace_id`/`span_id`) attached to a counter increment
+/// for trace-to-metric correlation under the OpenMetrics exposition format.
+#[derive(Clone, Debug)]
+pub struct Exemplar {
+    /// The exemplar's own label pairs, distinct from the series' labels.
+    pub labels: Vec<(String, String)>,
+    /// The value of the increment the exemplar was attached to.
+    pub value: f64,
+    /// Unix timestamp, in fractional seconds, of when the exemplar was recorded.
+    pub timestamp: f64,
+}
+
+impl Exemplar {
+    fn new(labels: Vec<(String, String)>, value: f64) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64();
+        Self {
+            labels,
+            value,
+            timestamp,
+        }
+    }
+}
+
+/// The most exemplars [`exemplar_store`] will retain at once, evicting the oldest-inserted entry
+/// (by insertion order, not recency of access) once the bound is exceeded. Bounds the store's
+/// memory in a long-running process that sees unbounded label cardinality, at the cost of
+/// potentially evicting an exemplar before it's ever scraped.
+const EXEMPLAR_STORE_CAPACITY: usize = 4096;
+
+/// A series is identified by its address plus, for a labeled series, a hash of its label
+/// combination -- two different label combinations on the same `IntCounterWithLabels` share an
+/// address but must not share an exemplar slot.
+type ExemplarKey = (usize, u64);
+
+/// A bounded, FIFO-evicting map from series identity to its most recently attached [`Exemplar`].
+#[derive(Default)]
+struct ExemplarStore {
+    entries: HashMap<ExemplarKey, Exemplar>,
+    insertion_order: VecDeque<ExemplarKey>,
+}
+
+impl ExemplarStore {
+    fn insert(&mut self, key: ExemplarKey, exemplar: Exemplar) {
+        if self.entries.insert(key, exemplar).is_none() {
+            self.insertion_order.push_back(key);
+            if self.insertion_order.len() > EXEMPLAR_STORE_CAPACITY {
+                if let Some(oldest) = self.insertion_order.pop_front() {
+                    self.entries.remove(&oldest);
+                }
+            }
+        }
+    }
+
+    fn get(&self, key: &ExemplarKey) -> Option<&Exemplar> {
+        self.entries.get(key)
+    }
+}
+
+/// Process-wide store of the most recently attached [`Exemplar`] for each series, keyed by the
+/// series' identity. An OpenMetrics encoder can consult [`exemplar_for`]/[`exemplar_for_labels`]
+/// when emitting a counter's sample.
+fn exemplar_store() -> &'static Mutex<ExemplarStore> {
+    static STORE: OnceLock<Mutex<ExemplarStore>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(ExemplarStore::default()))
+}
+
+/// Hash a labeled series' label combination into an [`ExemplarKey`]'s disambiguating component.
+fn label_combination_hash<L: Hash>(labels: &L) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    labels.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Record `labels`/`value` as the most recent exemplar for `series`, identified by its address.
+fn record_exemplar<T>(series: *const T, labels: Vec<(String, String)>, value: f64) {
+    record_exemplar_keyed(series, 0, labels, value);
+}
+
+/// Like [`record_exemplar`], but for a labeled series: `series_labels` disambiguates between the
+/// label combinations sharing `series`'s address.
+fn record_exemplar_labeled<T, L: Hash>(
+    series: *const T,
+    series_labels: &L,
+    labels: Vec<(String, String)>,
+    value: f64,
+) {
+    record_exemplar_keyed(series, label_combination_hash(series_labels), labels, value);
+}
+
+fn record_exemplar_keyed<T>(
+    series: *const T,
+    key_hash: u64,
+    labels: Vec<(String, String)>,
+    value: f64,
+) {
+    exemplar_store()
+        .lock()
+        .unwrap()
+        .insert((series as usize, key_hash), Exemplar::new(labels, value));
+}
+
+/// Look up the most recently attached exemplar for `series`, if any.
+pub fn exemplar_for<T>(series: *const T) -> Option<Exemplar> {
+    exemplar_store()
+        .lock()
+        .unwrap()
+        .get(&(series as usize, 0))
+        .cloned()
+}
+
+/// Like [`exemplar_for`], but for a labeled series where multiple label combinations share the
+/// same `IntCounterWithLabels` instance: `labels` disambiguates between them.
+pub fn exemplar_for_labels<T, L: Hash>(series: *const T, labels: &L) -> Option<Exemplar> {
+    exemplar_store()
+        .lock()
+        .unwrap()
+        .get(&(series as usize, label_combination_hash(labels)))
+        .cloned()
 }
 
 /// A guard that will automatically increment a labeled metric when dropped.
@@ -63,13 +391,38 @@ pub struct DeferredAddWithLabels<'a, L: Labels> {
     value: Option<u64>,
     metric: &'a IntCounterWithLabels<L>,
     labels: L,
+    // When this guard was created against a `PrunableCounterRegistry`-backed series, the
+    // increment goes to this handle (the registry's real atomic for that series) instead of
+    // `metric`, and holding it here keeps the series alive for the guard's lifetime so it can't
+    // be pruned out from under a pending increment.
+    handle: Option<AtomicCounterHandle>,
+    // Resolved eagerly in `via_hot_cache` (while an `L: PartialEq` bound is in scope on that
+    // method), rather than lazily here in `Drop` (which can't add bounds beyond the struct's
+    // own). A `Some` here always matched the hot combination, and is mirrored in addition to,
+    // never instead of, the real increment below.
+    hot_cache: Option<&'a HotLabelCache<L>>,
+    // The label hash is precomputed eagerly in `with_exemplar`, for the same reason: it lets
+    // that one method require `L: Hash` without forcing the bound onto every instantiation of
+    // this guard.
+    exemplar: Option<(u64, Vec<(String, String)>)>,
 }
 
 /// When dropped, a [`DeferredAddWithLabels`] guard will increment its counter.
 impl<'a, L: Labels> Drop for DeferredAddWithLabels<'a, L> {
     fn drop(&mut self) {
         if let Some(value) = self.value {
-            self.metric.add(value, &self.labels)
+            if let Some(cache) = self.hot_cache {
+                cache.mirror(value);
+            }
+            match &self.handle {
+                Some(handle) => handle.add(value),
+                None => self.metric.add(value, &self.labels),
+            }
+            if let Some((label_hash, exemplar)) = self.exemplar.take() {
+                // Always keyed on `metric`'s identity, even when `holding` diverted the
+                // increment to a registry handle -- see `with_exemplar`'s doc.
+                record_exemplar_keyed(self.metric, label_hash, exemplar, value as f64);
+            }
         }
     }
 }
@@ -84,6 +437,9 @@ impl<'a, L: Labels> DeferredAddWithLabels<'a, L> {
             value: Some(value),
             metric,
             labels,
+            handle: None,
+            hot_cache: None,
+            exemplar: None,
         }
     }
 
@@ -93,6 +449,54 @@ impl<'a, L: Labels> DeferredAddWithLabels<'a, L> {
         self
     }
 
+    /// Route the increment through a [`PrunableCounterRegistry`] handle instead of `metric`, and
+    /// keep the handle's series alive for as long as this guard exists, so it cannot be pruned
+    /// out from under a pending increment.
+    ///
+    /// This detaches the series from `metric`: the increment lands only in the registry's own
+    /// atomic, so it will not show up in a normal scrape of `metric`, only via the registry's own
+    /// [`PrunableCounterRegistry::snapshot`].
+    pub fn holding(mut self, handle: AtomicCounterHandle) -> DeferredAddWithLabels<'a, L> {
+        self.handle = Some(handle);
+        self
+    }
+
+    /// If this guard's labels match `cache`'s registered hot combination, mirror the increment
+    /// into it in addition to the real increment below (not instead of it: `add`/`inc` still do
+    /// their hashmap lookup). Call this after [`with_labels`](Self::with_labels), since the match is resolved
+    /// immediately rather than at drop time.
+    pub fn via_hot_cache(mut self, cache: &'a HotLabelCache<L>) -> DeferredAddWithLabels<'a, L>
+    where
+        L: PartialEq,
+    {
+        if cache.matches(&self.labels) {
+            self.hot_cache = Some(cache);
+        }
+        self
+    }
+
+    /// Attach an exemplar (e.g. a `trace_id`/`span_id` pair) to be recorded alongside the
+    /// increment when this guard completes, for emission under OpenMetrics exposition.
+    ///
+    /// The exemplar is always recorded against `metric`'s identity. With
+    /// [`holding`](Self::holding) the actual increment goes to the registry handle instead, in
+    /// which case the exemplar describes a value `metric`'s own series never received.
+    /// [`via_hot_cache`](Self::via_hot_cache) does not affect this, as it only mirrors the
+    /// increment.
+    pub fn with_exemplar(
+        mut self,
+        labels: impl IntoIterator<Item = (String, String)>,
+    ) -> DeferredAddWithLabels<'a, L>
+    where
+        L: Hash,
+    {
+        self.exemplar = Some((
+            label_combination_hash(&self.labels),
+            labels.into_iter().collect(),
+        ));
+        self
+    }
+
     /// Eagerly perform the increment, consuming the guard.
     pub fn complete_add(self) {
         drop(self)
@@ -104,12 +508,114 @@ impl<'a, L: Labels> DeferredAddWithLabels<'a, L> {
     }
 }
 
+/// A strong, clonable handle to a single label combination's counter value inside a
+/// [`PrunableCounterRegistry`]. This *is* the series' real atomic -- incrementing the handle is
+/// incrementing the series, and once the last handle is dropped, [`PrunableCounterRegistry::prune`]
+/// will remove it so it no longer appears in [`PrunableCounterRegistry::snapshot`].
+#[derive(Clone)]
+pub struct AtomicCounterHandle(Arc<AtomicU64>);
+
+impl AtomicCounterHandle {
+    /// Increase the tracked value by 1.
+    pub fn inc(&self) {
+        self.add(1);
+    }
+
+    /// Increase the tracked value by `v`.
+    pub fn add(&self, v: u64) {
+        self.0.fetch_add(v, Ordering::Relaxed);
+    }
+
+    /// Read the current value.
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// A self-pruning, cardinality-bounded labeled counter: each label combination is a series
+/// stored as a [`Weak`] handle, the caller holds the corresponding [`AtomicCounterHandle`] (which
+/// wraps the series' real atomic) for as long as it's "live", and a periodic [`Self::prune`]
+/// sweep (driven off [`Self::needs_pruning`], or just called on a timer) drops entries whose
+/// strong count has fallen to zero so [`Self::snapshot`] -- and so a scrape built from it --
+/// no longer reports them.
+///
+/// `L` only needs to be a plain hashable, cloneable label-combination key -- it does not need to
+/// be [`Labels`], so this is usable standalone or wired into an `IntCounterWithLabels<L>` via
+/// [`DeferredAddWithLabels::holding`].
+///
+/// The registry is entirely standalone from any prometheus metric: its counts live only in the
+/// `Arc<AtomicU64>`s behind [`AtomicCounterHandle`]. Wiring a series into it via `.holding(...)`
+/// detaches that series' increments from the `IntCounterWithLabels` it was created against -- a
+/// normal scrape of that metric will not reflect them, only [`Self::snapshot`] does.
+pub struct PrunableCounterRegistry<L: Eq + Hash + Clone> {
+    series: Mutex<HashMap<L, Weak<AtomicU64>>>,
+    needs_pruning: AtomicBool,
+}
+
+impl<L: Eq + Hash + Clone> Default for PrunableCounterRegistry<L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<L: Eq + Hash + Clone> PrunableCounterRegistry<L> {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self {
+            series: Mutex::new(HashMap::new()),
+            needs_pruning: AtomicBool::new(false),
+        }
+    }
+
+    /// Get the handle for `labels`, creating a fresh series for it if one doesn't already exist
+    /// (or the previous one has been pruned away).
+    pub fn get_or_create(&self, labels: &L) -> AtomicCounterHandle {
+        let mut series = self.series.lock().unwrap();
+        if let Some(existing) = series.get(labels).and_then(Weak::upgrade) {
+            return AtomicCounterHandle(existing);
+        }
+        let value = Arc::new(AtomicU64::new(0));
+        series.insert(labels.clone(), Arc::downgrade(&value));
+        self.needs_pruning.store(true, Ordering::Relaxed);
+        AtomicCounterHandle(value)
+    }
+
+    /// Whether entries have been added since the last [`Self::prune`] call and a sweep may be
+    /// worthwhile. This is a hint, not a guarantee that dead entries are actually present.
+    pub fn needs_pruning(&self) -> bool {
+        self.needs_pruning.load(Ordering::Relaxed)
+    }
+
+    /// Remove every series whose strong count has fallen to zero, i.e. nobody holds an
+    /// [`AtomicCounterHandle`] for it any more.
+    pub fn prune(&self) {
+        let mut series = self.series.lock().unwrap();
+        series.retain(|_, weak| weak.strong_count() > 0);
+        self.needs_pruning.store(false, Ordering::Relaxed);
+    }
+
+    /// The current value of every live series, for an encoder to scrape. Series pruned by
+    /// [`Self::prune`] (or whose last handle has simply been dropped, even before the next
+    /// `prune`) are not included.
+    pub fn snapshot(&self) -> Vec<(L, u64)> {
+        let series = self.series.lock().unwrap();
+        series
+            .iter()
+            .filter_map(|(labels, weak)| {
+                weak.upgrade()
+                    .map(|strong| (labels.clone(), AtomicCounterHandle(strong).get()))
+            })
+            .collect()
+    }
+}
+
 /// A guard that will automatically increment a [`GenericCounter`] when dropped.
 ///
 /// Created by the methods on the [`DeferredCounter`] extension trait.
 pub struct DeferredAdd<'a, P: Atomic> {
     value: Option<P::T>,
     metric: &'a GenericCounter<P>,
+    exemplar: Option<Vec<(String, String)>>,
 }
 
 impl<'a, P: Atomic> DeferredAdd<'a, P> {
@@ -122,6 +628,13 @@ impl<'a, P: Atomic> DeferredAdd<'a, P> {
     pub fn cancel(&mut self) {
         self.value = None;
     }
+
+    /// Attach an exemplar (e.g. a `trace_id`/`span_id` pair) to be recorded alongside the
+    /// increment when this guard completes, for emission under OpenMetrics exposition.
+    pub fn with_exemplar(mut self, labels: impl IntoIterator<Item = (String, String)>) -> Self {
+        self.exemplar = Some(labels.into_iter().collect());
+        self
+    }
 }
 
 /// When dropped, a [`DeferredAdd`] guard will increment its counter.
@@ -129,6 +642,9 @@ impl<'a, P: Atomic> Drop for DeferredAdd<'a, P> {
     fn drop(&mut self) {
         if let Some(value) = self.value {
             self.metric.inc_by(value);
+            if let Some(exemplar) = self.exemplar.take() {
+                record_exemplar(self.metric, exemplar, value.into_f64());
+            }
         }
     }
 }
@@ -145,6 +661,20 @@ pub trait DeferredCounter<P: Atomic + 'static> {
     /// Increase the counter by `v` when the guard is dropped.
     #[must_use]
     fn deferred_add(&'static self, v: P::T) -> DeferredAdd<P>;
+
+    /// Like [`deferred_inc`](Self::deferred_inc), but holds an owned clone of the counter instead
+    /// of a `'static` reference, so it can guard a counter owned inside an `Arc` or constructed
+    /// at runtime.
+    #[must_use]
+    fn deferred_inc_owned(&self) -> DeferredAddOwned<P> {
+        self.deferred_add_owned(<P::T as Number>::from_i64(1))
+    }
+
+    /// Like [`deferred_add`](Self::deferred_add), but holds an owned clone of the counter instead
+    /// of a `'static` reference, so it can guard a counter owned inside an `Arc` or constructed at
+    /// runtime.
+    #[must_use]
+    fn deferred_add_owned(&self, v: P::T) -> DeferredAddOwned<P>;
 }
 
 impl<P: Atomic + 'static> DeferredCounter<P> for GenericCounter<P> {
@@ -152,6 +682,377 @@ impl<P: Atomic + 'static> DeferredCounter<P> for GenericCounter<P> {
         DeferredAdd {
             value: Some(v),
             metric: self,
+            exemplar: None,
+        }
+    }
+
+    fn deferred_add_owned(&self, v: P::T) -> DeferredAddOwned<P> {
+        DeferredAddOwned {
+            value: Some(v),
+            metric: self.clone(),
+        }
+    }
+}
+
+/// A guard like [`DeferredAdd`], except that it holds an owned clone of the counter (cheap, since
+/// a [`GenericCounter`] is internally an `Arc`) rather than a `'static` reference. It has no
+/// `with_exemplar`; no exemplar is recorded for its increments.
+///
+/// Created by the methods on the [`DeferredCounter`] extension trait.
+pub struct DeferredAddOwned<P: Atomic> {
+    value: Option<P::T>,
+    metric: GenericCounter<P>,
+}
+
+impl<P: Atomic> DeferredAddOwned<P> {
+    /// Eagerly perform the increment, consuming the guard.
+    pub fn complete_add(self) {
+        drop(self)
+    }
+
+    /// Cancel the increment; the guard then does nothing when dropped.
+    pub fn cancel(&mut self) {
+        self.value = None;
+    }
+}
+
+/// When dropped, a [`DeferredAddOwned`] guard will increment its counter.
+impl<P: Atomic> Drop for DeferredAddOwned<P> {
+    fn drop(&mut self) {
+        if let Some(value) = self.value {
+            self.metric.inc_by(value);
+        }
+    }
+}
+
+/// An RAII-style guard that observes the elapsed time into a [`Histogram`] when dropped.
+///
+/// Created by calling [`ObserveOnDrop::start_timer_guarded`].
+pub struct TimerGuard<'a> {
+    start: Instant,
+    histogram: &'a Histogram,
+    active: bool,
+}
+
+/// When dropped, a [`TimerGuard`] observes the elapsed time since it was created.
+impl<'a> Drop for TimerGuard<'a> {
+    fn drop(&mut self) {
+        if self.active {
+            self.histogram.observe(self.start.elapsed().as_secs_f64());
+        }
+    }
+}
+
+impl<'a> TimerGuard<'a> {
+    /// Eagerly observe the elapsed time, consuming the guard.
+    pub fn complete(self) {
+        drop(self)
+    }
+
+    /// Cancel the observation; the guard then observes nothing when dropped.
+    pub fn cancel(&mut self) {
+        self.active = false;
+    }
+}
+
+/// An extension trait for [`Histogram`] to provide a method for timing a scope and recording its
+/// duration as an RAII-style guard.
+pub trait ObserveOnDrop {
+    /// Start a timer that, when dropped, observes the elapsed seconds into this histogram.
+    #[must_use]
+    fn start_timer_guarded(&'static self) -> TimerGuard<'static>;
+}
+
+impl ObserveOnDrop for Histogram {
+    fn start_timer_guarded(&'static self) -> TimerGuard<'static> {
+        TimerGuard {
+            start: Instant::now(),
+            histogram: self,
+            active: true,
+        }
+    }
+}
+
+/// An RAII-style guard that observes the elapsed time into a labeled [`HistogramVec`] series when
+/// dropped.
+///
+/// Created by calling [`ObserveOnDropWithLabels::start_timer_guarded_with_labels`].
+pub struct TimerGuardWithLabels<'a> {
+    start: Instant,
+    histogram: &'a HistogramVec,
+    label_values: Vec<String>,
+    active: bool,
+}
+
+/// When dropped, a [`TimerGuardWithLabels`] observes the elapsed time since it was created.
+impl<'a> Drop for TimerGuardWithLabels<'a> {
+    fn drop(&mut self) {
+        if self.active {
+            let label_values: Vec<&str> = self.label_values.iter().map(String::as_str).collect();
+            self.histogram
+                .with_label_values(&label_values)
+                .observe(self.start.elapsed().as_secs_f64());
+        }
+    }
+}
+
+impl<'a> TimerGuardWithLabels<'a> {
+    /// Eagerly observe the elapsed time, consuming the guard.
+    pub fn complete(self) {
+        drop(self)
+    }
+
+    /// Cancel the observation; the guard then observes nothing when dropped.
+    pub fn cancel(&mut self) {
+        self.active = false;
+    }
+}
+
+/// An extension trait for [`HistogramVec`] to provide a method for timing a scope and recording
+/// its duration, for a given set of label values, as an RAII-style guard.
+pub trait ObserveOnDropWithLabels {
+    /// Start a timer that, when dropped, observes the elapsed seconds into the series identified
+    /// by `label_values`.
+    #[must_use]
+    fn start_timer_guarded_with_labels<'a>(
+        &'a self,
+        label_values: &[&str],
+    ) -> TimerGuardWithLabels<'a>;
+}
+
+impl ObserveOnDropWithLabels for HistogramVec {
+    fn start_timer_guarded_with_labels<'a>(
+        &'a self,
+        label_values: &[&str],
+    ) -> TimerGuardWithLabels<'a> {
+        TimerGuardWithLabels {
+            start: Instant::now(),
+            histogram: self,
+            label_values: label_values.iter().map(|s| s.to_string()).collect(),
+            active: true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prometheus::{HistogramOpts, IntGauge};
+
+    #[test]
+    fn gauge_guard_tracks_mutations_and_nets_to_zero_on_drop() {
+        let gauge: &'static IntGauge = Box::leak(Box::new(IntGauge::new("g1", "help").unwrap()));
+        {
+            let mut guard = gauge.guarded_inc();
+            assert_eq!(gauge.get(), 1);
+
+            guard.add(4);
+            assert_eq!(gauge.get(), 5);
+
+            guard.set(10);
+            assert_eq!(gauge.get(), 10);
+
+            guard.sub(3);
+            assert_eq!(gauge.get(), 7);
+        }
+        assert_eq!(gauge.get(), 0);
+    }
+
+    #[test]
+    fn gauge_guard_accumulates_integers_exactly_above_f64_precision() {
+        // 2^53 + 1 can't be represented exactly as an `f64`; the guard must not round-trip
+        // through one when accumulating `i64` deltas.
+        let large = (1i64 << 53) + 1;
+        let gauge: &'static IntGauge = Box::leak(Box::new(IntGauge::new("g2", "help").unwrap()));
+        {
+            let mut guard = gauge.guarded_add(large);
+            guard.add(1);
+            assert_eq!(gauge.get(), large + 1);
+        }
+        assert_eq!(gauge.get(), 0);
+    }
+
+    #[test]
+    fn owned_gauge_guard_tracks_mutations_and_nets_to_zero_on_drop() {
+        let gauge = IntGauge::new("g3", "help").unwrap();
+        {
+            let mut guard = gauge.guarded_inc_owned();
+            assert_eq!(gauge.get(), 1);
+
+            guard.add(4);
+            assert_eq!(gauge.get(), 5);
+
+            guard.set(10);
+            assert_eq!(gauge.get(), 10);
+
+            guard.sub(3);
+            assert_eq!(gauge.get(), 7);
+        }
+        assert_eq!(gauge.get(), 0);
+    }
+
+    #[test]
+    fn owned_gauge_guard_keeps_working_after_the_gauge_binding_is_gone() {
+        // The whole point of the owned guard: no `'static` borrow of `gauge` is required, so it
+        // can be dropped from its original binding and still outlive it via the guard's clone.
+        let guard = {
+            let gauge = IntGauge::new("g4", "help").unwrap();
+            gauge.guarded_add_owned(3)
+        };
+        drop(guard);
+    }
+
+    #[test]
+    fn deferred_add_owned_increments_on_drop_unless_cancelled() {
+        use prometheus::IntCounter;
+
+        let counter = IntCounter::new("c1", "help").unwrap();
+        counter.deferred_inc_owned().complete_add();
+        assert_eq!(counter.get(), 1);
+
+        let mut guard = counter.deferred_add_owned(5);
+        guard.cancel();
+        drop(guard);
+        assert_eq!(counter.get(), 1);
+    }
+
+    #[derive(Debug, Eq, PartialEq, Hash, Clone)]
+    struct TestLabels(&'static str);
+
+    #[test]
+    fn prunable_registry_prunes_only_series_with_no_live_handle() {
+        let registry = PrunableCounterRegistry::<TestLabels>::new();
+
+        let a = registry.get_or_create(&TestLabels("a"));
+        a.add(2);
+        {
+            let b = registry.get_or_create(&TestLabels("b"));
+            b.inc();
+            assert_eq!(registry.snapshot().len(), 2);
+        }
+        // `b`'s only handle just went out of scope; it's dead but not yet pruned.
+        assert!(registry.needs_pruning());
+
+        registry.prune();
+        let snapshot = registry.snapshot();
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(snapshot[0], (TestLabels("a"), 2));
+        assert!(!registry.needs_pruning());
+
+        drop(a);
+    }
+
+    #[test]
+    fn prunable_registry_get_or_create_reuses_the_live_series() {
+        let registry = PrunableCounterRegistry::<TestLabels>::new();
+        let first = registry.get_or_create(&TestLabels("a"));
+        first.add(3);
+        let second = registry.get_or_create(&TestLabels("a"));
+        second.inc();
+        assert_eq!(first.get(), 4);
+    }
+
+    #[test]
+    fn hot_label_cache_matches_only_the_registered_combination() {
+        let cache = HotLabelCache::new(TestLabels("a"));
+        assert!(cache.matches(&TestLabels("a")));
+        assert!(!cache.matches(&TestLabels("b")));
+    }
+
+    #[test]
+    fn hot_label_cache_mirror_accumulates_regardless_of_match() {
+        let cache = HotLabelCache::new(TestLabels("a"));
+        cache.mirror(2);
+        cache.mirror(3);
+        assert_eq!(cache.get(), 5);
+    }
+
+    #[test]
+    fn exemplar_round_trips_through_record_and_lookup() {
+        let marker = 1u8;
+        let ptr = &marker as *const u8;
+        record_exemplar(ptr, vec![("trace_id".to_string(), "abc".to_string())], 2.0);
+
+        let exemplar = exemplar_for(ptr).expect("exemplar was recorded");
+        assert_eq!(exemplar.value, 2.0);
+        assert_eq!(
+            exemplar.labels,
+            vec![("trace_id".to_string(), "abc".to_string())]
+        );
+    }
+
+    #[test]
+    fn exemplar_for_labels_disambiguates_label_combinations_on_the_same_series() {
+        let marker = 2u8;
+        let ptr = &marker as *const u8;
+        record_exemplar_labeled(ptr, &TestLabels("a"), vec![], 1.0);
+        record_exemplar_labeled(ptr, &TestLabels("b"), vec![], 2.0);
+
+        let a = exemplar_for_labels(ptr, &TestLabels("a")).expect("exemplar for a");
+        let b = exemplar_for_labels(ptr, &TestLabels("b")).expect("exemplar for b");
+        assert_eq!(a.value, 1.0);
+        assert_eq!(b.value, 2.0);
+    }
+
+    #[test]
+    fn exemplar_store_evicts_the_oldest_entry_once_over_capacity() {
+        let mut store = ExemplarStore::default();
+        for i in 0..=EXEMPLAR_STORE_CAPACITY {
+            store.insert((i, 0), Exemplar::new(vec![], i as f64));
+        }
+        assert!(store.get(&(0, 0)).is_none());
+        assert!(store.get(&(EXEMPLAR_STORE_CAPACITY, 0)).is_some());
+    }
+
+    #[test]
+    fn timer_guard_observes_elapsed_time_on_drop() {
+        let histogram: &'static Histogram = Box::leak(Box::new(
+            Histogram::with_opts(HistogramOpts::new("t1", "help")).unwrap(),
+        ));
+        {
+            let _guard = histogram.start_timer_guarded();
+        }
+        assert_eq!(histogram.get_sample_count(), 1);
+    }
+
+    #[test]
+    fn timer_guard_cancel_skips_the_observation() {
+        let histogram: &'static Histogram = Box::leak(Box::new(
+            Histogram::with_opts(HistogramOpts::new("t2", "help")).unwrap(),
+        ));
+        {
+            let mut guard = histogram.start_timer_guarded();
+            guard.cancel();
+        }
+        assert_eq!(histogram.get_sample_count(), 0);
+    }
+
+    #[test]
+    fn timer_guard_with_labels_observes_into_the_right_series() {
+        let histogram_vec = HistogramVec::new(HistogramOpts::new("t3", "help"), &["kind"]).unwrap();
+        {
+            let _guard = histogram_vec.start_timer_guarded_with_labels(&["a"]);
+        }
+        assert_eq!(
+            histogram_vec.with_label_values(&["a"]).get_sample_count(),
+            1
+        );
+        assert_eq!(
+            histogram_vec.with_label_values(&["b"]).get_sample_count(),
+            0
+        );
+    }
+
+    #[test]
+    fn timer_guard_with_labels_cancel_skips_the_observation() {
+        let histogram_vec = HistogramVec::new(HistogramOpts::new("t4", "help"), &["kind"]).unwrap();
+        {
+            let mut guard = histogram_vec.start_timer_guarded_with_labels(&["a"]);
+            guard.cancel();
         }
+        assert_eq!(
+            histogram_vec.with_label_values(&["a"]).get_sample_count(),
+            0
+        );
     }
 }